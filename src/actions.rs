@@ -1,32 +1,73 @@
 use hyper::http::Method;
 use hyper::Uri;
+use serde::Deserialize;
 use std::collections::BTreeMap;
-/// Generate the action `BTreeMap`
+
+/// Generate the built-in action `BTreeMap`
 ///
-/// Modify this function to add or remove sidecar definitions and their associated shutdown procedures.
+/// These are the definitions used when no `--config` file is given; see
+/// `crate::config::load` for the file-backed alternative.
 pub fn generate() -> BTreeMap<String, Action> {
     BTreeMap::from([
         (
             "cloudsql-proxy".into(),
-            Action::Portforward(Method::POST, "/quitquitquit".parse::<Uri>().unwrap(), 9091),
+            Action {
+                action_type: ActionType::Portforward,
+                command: None,
+                method: Some(Method::POST),
+                path: Some("/quitquitquit".parse::<Uri>().unwrap()),
+                port: Some(9091),
+            },
         ),
         (
             "vks-sidecar".into(),
-            Action::Exec("/bin/kill -s INT 1".split(' ').map(String::from).collect()),
+            Action {
+                action_type: ActionType::Exec,
+                command: Some("/bin/kill -s INT 1".into()),
+                method: None,
+                path: None,
+                port: None,
+            },
         ),
         (
             "istio-proxy".into(),
-            Action::Portforward(Method::POST, "/quitquitquit".parse::<Uri>().unwrap(), 15000),
+            Action {
+                action_type: ActionType::Portforward,
+                command: None,
+                method: Some(Method::POST),
+                path: Some("/quitquitquit".parse::<Uri>().unwrap()),
+                port: Some(15000),
+            },
         ),
         (
             "linkerd-proxy".into(),
-            Action::Portforward(Method::POST, "/shutdown".parse::<Uri>().unwrap(), 4191),
+            Action {
+                action_type: ActionType::Portforward,
+                command: None,
+                method: Some(Method::POST),
+                path: Some("/shutdown".parse::<Uri>().unwrap()),
+                port: Some(4191),
+            },
         ),
     ])
 }
 
+/// The shutdown procedure for a single sidecar container
+///
+/// Only the fields relevant to `action_type` are populated: `command` for
+/// `ActionType::Exec`, `method`/`path`/`port` for `ActionType::Portforward`.
 #[derive(Debug)]
-pub enum Action {
-    Portforward(Method, Uri, u16),
-    Exec(Vec<String>),
+pub struct Action {
+    pub action_type: ActionType,
+    pub command: Option<String>,
+    pub method: Option<Method>,
+    pub path: Option<Uri>,
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionType {
+    Exec,
+    Portforward,
 }
@@ -1,16 +1,68 @@
 use crate::actions::{Action, ActionType};
+use crate::prometheus::SIDECAR_SHUTDOWN_ATTEMPTS;
 use async_trait::async_trait;
-use hyper::{body, Body, Request};
+use hyper::{body, Body, Request, StatusCode};
 use k8s_openapi::api::core::v1::Pod;
 use kube::api::{Api, AttachParams};
-use tracing::{error, info};
+use rand::Rng;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+/// Maximum number of attempts made to shut down a single sidecar before giving up
+const MAX_ATTEMPTS: u32 = 5;
+/// Delay before the first retry; doubled on each subsequent attempt
+const BASE_DELAY: Duration = Duration::from_millis(250);
+/// Upper bound on the (pre-jitter) backoff delay
+const MAX_DELAY: Duration = Duration::from_secs(4);
+
+/// Errors that can occur while trying to shut down a sidecar
+///
+/// This distinguishes attempts worth retrying (a connection hiccup, or the
+/// proxy's own 5xx response) from terminal failures (a 4xx from the quit
+/// endpoint means the request itself is wrong, so retrying won't help).
+#[derive(Debug, Error)]
+enum ShutdownError {
+    #[error("kubernetes API error: {0}")]
+    Kube(#[from] kube::Error),
+    #[error("connection error: {0}")]
+    Connection(#[from] hyper::Error),
+    #[error("quit endpoint returned client error {0}: {1}")]
+    ClientError(StatusCode, String),
+    #[error("quit endpoint returned server error {0}: {1}")]
+    ServerError(StatusCode, String),
+}
+
+impl ShutdownError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            ShutdownError::ClientError(_, _) => false,
+            ShutdownError::ServerError(_, _) => true,
+            ShutdownError::Connection(_) => true,
+            // A 4xx from the Kubernetes API (403 forbidden, 404 no such container, ...) is a
+            // permanent misconfiguration, not a transient hiccup, so only a 5xx is retried.
+            ShutdownError::Kube(kube::Error::Api(resp)) => resp.code >= 500,
+            ShutdownError::Kube(_) => true,
+        }
+    }
+}
+
+/// Exponential backoff with jitter for the `attempt`'th retry (1-indexed)
+fn backoff(attempt: u32) -> Duration {
+    let delay = BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+    let delay = delay.min(MAX_DELAY);
+    let jitter_ms = rand::thread_rng().gen_range(0..=delay.as_millis() as u64 / 2);
+    delay + Duration::from_millis(jitter_ms)
+}
 
 /// Shutdown method for Apis with type Pod
 #[async_trait]
 pub trait Destroyer {
     /// Shuts down a container in a given pod with a given Action
-    /// 
-    /// This is the primary public facing business function for this application
+    ///
+    /// Retries transient failures with bounded exponential backoff; this is
+    /// the primary public facing business function for this application
     async fn shutdown(&self, action: &Action, pod_name: &str, container_name: &str) -> anyhow::Result<()>;
 }
 
@@ -26,12 +78,38 @@ trait DestroyerActions {
 #[async_trait]
 impl Destroyer for Api<Pod> {
     async fn shutdown(&self, action: &Action, pod_name: &str, container_name: &str) -> anyhow::Result<()> {
-        match action.action_type {
-            ActionType::Exec => self.shutdown_exec(action, pod_name, container_name).await?,
-            ActionType::Portforward => self.shutdown_portforward(action, pod_name, container_name).await?,
-            _ => (),
-        };
-        Ok(())
+        let mut attempt = 0u32;
+        loop {
+            attempt += 1;
+            let res = match action.action_type {
+                ActionType::Exec => self.shutdown_exec(action, pod_name, container_name).await,
+                ActionType::Portforward => self.shutdown_portforward(action, pod_name, container_name).await,
+            };
+            let err = match res {
+                Ok(()) => {
+                    SIDECAR_SHUTDOWN_ATTEMPTS
+                        .with_label_values(&[container_name, "success"])
+                        .observe(attempt as f64);
+                    return Ok(());
+                }
+                Err(err) => err,
+            };
+
+            let retryable = err
+                .downcast_ref::<ShutdownError>()
+                .map(ShutdownError::is_retryable)
+                .unwrap_or(true);
+            if !retryable || attempt >= MAX_ATTEMPTS {
+                SIDECAR_SHUTDOWN_ATTEMPTS
+                    .with_label_values(&[container_name, "failure"])
+                    .observe(attempt as f64);
+                return Err(err);
+            }
+
+            let delay = backoff(attempt);
+            warn!("Attempt {attempt} to shut down {container_name}@{pod_name} failed: {err}; retrying in {delay:?}");
+            sleep(delay).await;
+        }
     }
 }
 
@@ -39,36 +117,30 @@ impl Destroyer for Api<Pod> {
 impl DestroyerActions for Api<Pod> {
     async fn shutdown_exec(&self, action: &Action, pod_name: &str, container_name: &str) -> anyhow::Result<()> {
         let command: Vec<&str> = action.command.as_ref().unwrap().split(' ').collect();
-        match self
-            .exec(
-                pod_name,
-                command,
-                &AttachParams::default().container(container_name).stdout(false),
-            )
-            .await
-        {
-            Ok(_) => info!(
-                "Sent `{}` to {}@{}",
-                action.command.as_ref().unwrap(),
-                container_name,
-                pod_name
-            ),
-            Err(err) => {
-                error!(
-                    "Something bad happened while trying to exec into {}@{}: {}",
-                    container_name, pod_name, err
-                );
-            }
-        };
+        self.exec(
+            pod_name,
+            command,
+            &AttachParams::default().container(container_name).stdout(false),
+        )
+        .await
+        .map_err(ShutdownError::Kube)?;
+        info!(
+            "Sent `{}` to {}@{}",
+            action.command.as_ref().unwrap(),
+            container_name,
+            pod_name
+        );
         Ok(())
     }
 
     async fn shutdown_portforward(&self, action: &Action, pod_name: &str, container_name: &str) -> anyhow::Result<()> {
         let port = action.port.unwrap();
-        let mut pf = self.portforward(pod_name, &[port]).await?;
+        let mut pf = self.portforward(pod_name, &[port]).await.map_err(ShutdownError::Kube)?;
         let pf_ports = pf.ports();
         let stream = pf_ports[0].stream().unwrap();
-        let (mut sender, connection) = hyper::client::conn::handshake(stream).await?;
+        let (mut sender, connection) = hyper::client::conn::handshake(stream)
+            .await
+            .map_err(ShutdownError::Connection)?;
         tokio::spawn(async move {
             if let Err(e) = connection.await {
                 error!("Error in connection: {}", e);
@@ -82,12 +154,12 @@ impl DestroyerActions for Api<Pod> {
             .body(Body::from(""))
             .unwrap();
 
-        let (parts, body) = sender.send_request(req).await?.into_parts();
-        if parts.status != 200 {
-            let body_bytes = body::to_bytes(body).await?;
-            let body_str = std::str::from_utf8(&body_bytes)?;
-            error!("HTTP request failed: code {}: {}", parts.status, body_str)
-        } else {
+        let (parts, body) = sender
+            .send_request(req)
+            .await
+            .map_err(ShutdownError::Connection)?
+            .into_parts();
+        if parts.status.is_success() {
             info!(
                 "Sent `{} {}` at port {} to {} ({})",
                 action.method.as_ref().unwrap(),
@@ -95,8 +167,17 @@ impl DestroyerActions for Api<Pod> {
                 port,
                 pod_name,
                 container_name
-            )
+            );
+            return Ok(());
+        }
+
+        let body_bytes = body::to_bytes(body).await.map_err(ShutdownError::Connection)?;
+        let body_str = String::from_utf8_lossy(&body_bytes).into_owned();
+        error!("HTTP request failed: code {}: {}", parts.status, body_str);
+        if parts.status.is_client_error() {
+            Err(ShutdownError::ClientError(parts.status, body_str).into())
+        } else {
+            Err(ShutdownError::ServerError(parts.status, body_str).into())
         }
-        Ok(())
     }
 }
@@ -0,0 +1,28 @@
+use clap::Parser;
+use hyper::Uri;
+use std::path::PathBuf;
+
+/// hahaha watches naisjob Pods and shuts down their sidecars once the main container exits
+#[derive(Debug, Parser)]
+#[command(author, version, about)]
+pub struct Cli {
+    /// Port to serve Prometheus metrics on
+    #[arg(long, default_value_t = 8999)]
+    pub prometheus_port: u16,
+
+    /// Label selector used to find Pods to watch, in `kubectl get -l` syntax
+    #[arg(long, default_value = "nais.io/naisjob=true")]
+    pub label_selector: String,
+
+    /// Restrict the watcher to a single namespace instead of the whole cluster
+    #[arg(long)]
+    pub namespace: Option<String>,
+
+    /// Path to a sidecar config file; falls back to the built-in defaults when omitted
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Webhook to POST a JSON payload to on a failed sidecar shutdown; no notification is sent when omitted
+    #[arg(long)]
+    pub webhook_url: Option<Uri>,
+}
@@ -0,0 +1,104 @@
+use crate::actions::{Action, ActionType};
+use anyhow::{bail, Context};
+use hyper::http::Method;
+use hyper::Uri;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// On-disk representation of a single sidecar's shutdown definition.
+///
+/// `command` only applies to `ActionType::Exec`; `method`/`path`/`port` only
+/// apply to `ActionType::Portforward`. [`RawAction::into_action`] keeps only
+/// the fields that apply to the entry's `action_type` (any others are ignored,
+/// not rejected) and fails fast when a required field is missing or unparseable.
+#[derive(Debug, Deserialize)]
+struct RawAction {
+    action_type: ActionType,
+    command: Option<String>,
+    method: Option<String>,
+    path: Option<String>,
+    port: Option<u16>,
+}
+
+impl RawAction {
+    fn into_action(self, container_name: &str) -> anyhow::Result<Action> {
+        match self.action_type {
+            ActionType::Exec => {
+                let command = self
+                    .command
+                    .with_context(|| format!("{container_name}: `exec` action requires a `command`"))?;
+                Ok(Action {
+                    action_type: ActionType::Exec,
+                    command: Some(command),
+                    method: None,
+                    path: None,
+                    port: None,
+                })
+            }
+            ActionType::Portforward => {
+                let port = self
+                    .port
+                    .with_context(|| format!("{container_name}: `portforward` action requires a `port`"))?;
+                let method = match self.method {
+                    Some(m) => {
+                        Method::from_str(&m).with_context(|| format!("{container_name}: invalid HTTP method `{m}`"))?
+                    }
+                    None => Method::POST,
+                };
+                let path = match self.path {
+                    Some(p) => p
+                        .parse::<Uri>()
+                        .with_context(|| format!("{container_name}: invalid path `{p}`"))?,
+                    None => bail!("{container_name}: `portforward` action requires a `path`"),
+                };
+                Ok(Action {
+                    action_type: ActionType::Portforward,
+                    command: None,
+                    method: Some(method),
+                    path: Some(path),
+                    port: Some(port),
+                })
+            }
+        }
+    }
+}
+
+/// Typed representation of the sidecar config file.
+///
+/// Maps a container name (as it appears in the Pod spec) to its shutdown
+/// definition. See [`load`] for how this is read and validated.
+#[derive(Debug, Deserialize)]
+struct Config {
+    #[serde(flatten)]
+    sidecars: BTreeMap<String, RawAction>,
+}
+
+/// Load sidecar shutdown definitions from `path`.
+///
+/// Falls back to `actions::generate`'s built-in defaults when `path` is
+/// `None`. Every entry is validated as it's loaded (e.g. a `portforward`
+/// action missing a `port`, or an unparseable method/URI) so a misconfigured
+/// file fails fast at startup instead of panicking later in
+/// `shutdown_portforward`.
+pub fn load(path: Option<&Path>) -> anyhow::Result<BTreeMap<String, Action>> {
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(crate::actions::generate()),
+    };
+
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("reading config file {}", path.display()))?;
+    let config: Config =
+        toml::from_str(&contents).with_context(|| format!("parsing config file {}", path.display()))?;
+
+    config
+        .sidecars
+        .into_iter()
+        .map(|(name, raw)| {
+            let action = raw.into_action(&name)?;
+            Ok((name, action))
+        })
+        .collect()
+}
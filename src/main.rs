@@ -9,26 +9,37 @@ use kube::{
     Client, Resource, ResourceExt,
 };
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::signal::unix::{signal, SignalKind};
 use tokio::sync::Notify;
 use tracing::{error, info, warn};
 
 mod actions;
 mod api;
+mod cli;
+mod config;
 mod events;
+mod notify;
 mod pod;
 mod prometheus;
 
-use crate::{api::Destroyer, events::Recorder, pod::Sidecars, prometheus::*};
+use crate::{api::Destroyer, cli::Cli, events::Recorder, notify::Notifier, pod::Sidecars, prometheus::*};
+use clap::Parser;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt().json().try_init().unwrap();
 
-    let actions = actions::generate();
+    let cli = Cli::parse();
+
+    let actions = config::load(cli.config.as_deref())?;
     let client = Client::try_default().await?;
 
-    let pods: Api<Pod> = Api::all(client.clone());
-    let lp = ListParams::default().labels("nais.io/naisjob=true");
+    let pods: Api<Pod> = match &cli.namespace {
+        Some(namespace) => Api::namespaced(client.clone(), namespace),
+        None => Api::all(client.clone()),
+    };
+    let lp = ListParams::default().labels(&cli.label_selector);
 
     let h = hostname::get()?;
     let host_name = match h.to_str() {
@@ -41,15 +52,41 @@ async fn main() -> anyhow::Result<()> {
         instance: Some(host_name.into()),
     };
 
-    let mut ew = try_flatten_applied(watcher(pods, lp)).boxed();
+    let notifier = Notifier::new(cli.webhook_url.clone());
+
+    // the watcher is ready once it has completed its first list/watch round-trip,
+    // whether or not that round-trip turned up any matching Pods
+    let ready = Readiness::new();
+    let ready_for_watcher = ready.clone();
+    let mut ew = try_flatten_applied(watcher(pods, lp).inspect_ok(move |_| ready_for_watcher.set_ready())).boxed();
+    let mut sigterm = signal(SignalKind::terminate())?;
 
     let shutdown = Arc::new(Notify::new());
     let shutdown_clone = shutdown.clone();
+    let ready_clone = ready.clone();
+    let prometheus_port = cli.prometheus_port;
     let prom = tokio::spawn(async move {
-        prometheus_server(8999, shutdown_clone.notified()).await.unwrap();
+        prometheus_server(prometheus_port, shutdown_clone.notified(), ready_clone)
+            .await
+            .unwrap();
     });
 
-    while let Some(pod) = ew.try_next().await? {
+    loop {
+        let pod = tokio::select! {
+            next = ew.try_next() => match next? {
+                Some(pod) => pod,
+                None => break,
+            },
+            _ = tokio::signal::ctrl_c() => {
+                info!("received SIGINT, letting in-flight shutdowns finish before exiting");
+                break;
+            },
+            _ = sigterm.recv() => {
+                info!("received SIGTERM, letting in-flight shutdowns finish before exiting");
+                break;
+            },
+        };
+
         let pod_name = pod.name();
 
         let running_sidecars = pod.sidecars().unwrap_or_else(|err| {
@@ -101,6 +138,13 @@ async fn main() -> anyhow::Result<()> {
                     error!("Couldn't publish Kubernetes Event: {e}");
                     TOTAL_UNSUCCESSFUL_EVENT_POSTS.inc();
                 }
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or_default();
+                notifier
+                    .notify_failure(&pod_name, &namespace, &job_name, &sidecar_name, &err.to_string(), timestamp)
+                    .await;
                 FAILED_SIDECAR_SHUTDOWNS
                     .with_label_values(&[&sidecar_name, &job_name, &namespace])
                     .inc();
@@ -116,7 +160,7 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
-    // we're likely not ever reaching down here, but let's be nice about it if we do
+    // stop serving metrics and wait for the server to drain its connections
     shutdown.notify_one();
     prom.await?;
     Ok(())
@@ -0,0 +1,91 @@
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Method, Request, Uri};
+use hyper_tls::HttpsConnector;
+use serde::Serialize;
+use tracing::error;
+
+/// JSON payload POSTed to the configured webhook on a failed sidecar shutdown
+#[derive(Debug, Serialize)]
+struct FailurePayload<'a> {
+    pod_name: &'a str,
+    namespace: &'a str,
+    job_name: &'a str,
+    container_name: &'a str,
+    error: &'a str,
+    timestamp: i64,
+}
+
+/// Notifies an external webhook (Slack/Alertmanager-style receiver) about failed sidecar shutdowns
+///
+/// A `Notifier` with no webhook URL configured is a no-op; this is the
+/// default so operators who don't want out-of-cluster alerts don't pay for
+/// them. The client is HTTPS-capable since Slack and Alertmanager receivers
+/// are typically `https://` endpoints.
+#[derive(Clone)]
+pub struct Notifier {
+    webhook_url: Option<Uri>,
+    client: Client<HttpsConnector<HttpConnector>, Body>,
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Notifier {
+    pub fn new(webhook_url: Option<Uri>) -> Self {
+        Self {
+            webhook_url,
+            client: Client::builder().build(HttpsConnector::new()),
+        }
+    }
+
+    /// POSTs details of a failed sidecar shutdown to the configured webhook
+    ///
+    /// Does nothing if no webhook URL was configured. Failures to reach the
+    /// webhook itself are only logged, since the shutdown failure has
+    /// already been recorded via the Kubernetes Event/metrics path.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn notify_failure(
+        &self,
+        pod_name: &str,
+        namespace: &str,
+        job_name: &str,
+        container_name: &str,
+        error: &str,
+        timestamp: i64,
+    ) {
+        let webhook_url = match &self.webhook_url {
+            Some(webhook_url) => webhook_url,
+            None => return,
+        };
+
+        let payload = FailurePayload {
+            pod_name,
+            namespace,
+            job_name,
+            container_name,
+            error,
+            timestamp,
+        };
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                error!("Couldn't serialize webhook payload: {e}");
+                return;
+            }
+        };
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(webhook_url.clone())
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+
+        if let Err(e) = self.client.request(req).await {
+            error!("Couldn't post shutdown failure to webhook {webhook_url}: {e}");
+        }
+    }
+}
@@ -1,7 +1,12 @@
 use futures::Future;
 use hyper::service::{make_service_fn, service_fn};
-use hyper::{server::Server, Body, Request, Response};
-use prometheus::{register_int_counter, register_int_counter_vec, Encoder, IntCounter, IntCounterVec, TextEncoder};
+use hyper::{server::Server, Body, Method, Request, Response, StatusCode};
+use prometheus::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, Encoder, HistogramVec, IntCounter,
+    IntCounterVec, TextEncoder,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tracing::{error, info};
 
 lazy_static! {
@@ -28,31 +33,89 @@ lazy_static! {
         &["container", "job_name", "namespace"],
     )
     .unwrap();
+    pub static ref SIDECAR_SHUTDOWN_ATTEMPTS: HistogramVec = register_histogram_vec!(
+        "hahaha_sidecar_shutdown_attempts",
+        "Number of attempts made to shut down a sidecar, including retries, by outcome",
+        &["container", "outcome"],
+    )
+    .unwrap();
+}
+
+/// Whether the kube watcher has successfully established its stream
+///
+/// Cloning shares the same underlying flag; `set_ready` is idempotent, so
+/// the watcher loop can call it on every event with no extra bookkeeping.
+#[derive(Clone, Default)]
+pub struct Readiness(Arc<AtomicBool>);
+
+impl Readiness {
+    pub fn new() -> Self {
+        Self(Arc::new(AtomicBool::new(false)))
+    }
+
+    pub fn set_ready(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
 }
 
-/// The function which triggers on any request to the server (incl. any path)
-async fn metric_service(_req: Request<Body>) -> hyper::Result<Response<Body>> {
+fn empty_response(status: StatusCode) -> Response<Body> {
+    Response::builder().status(status).body(Body::empty()).unwrap()
+}
+
+/// Serves the Prometheus text dump
+async fn metric_service() -> Response<Body> {
     let encoder = TextEncoder::new();
     let mut buffer = vec![];
     let mf = prometheus::gather();
     encoder.encode(&mf, &mut buffer).unwrap();
-    Ok(Response::builder()
+    Response::builder()
         .header(hyper::header::CONTENT_TYPE, encoder.format_type())
         .body(Body::from(buffer))
-        .unwrap())
+        .unwrap()
+}
+
+/// Dispatches a request to `/metrics` (aliased at `/`), `/healthz`, or `/readyz`
+///
+/// Anything else gets a `404`, and any non-`GET` method gets a `405`.
+async fn route(req: Request<Body>, ready: Readiness) -> hyper::Result<Response<Body>> {
+    if req.method() != Method::GET {
+        return Ok(empty_response(StatusCode::METHOD_NOT_ALLOWED));
+    }
+    let res = match req.uri().path() {
+        "/metrics" | "/" => metric_service().await,
+        "/healthz" => Response::new(Body::from("ok")),
+        "/readyz" => {
+            if ready.is_ready() {
+                Response::new(Body::from("ok"))
+            } else {
+                let mut res = empty_response(StatusCode::SERVICE_UNAVAILABLE);
+                *res.body_mut() = Body::from("not ready");
+                res
+            }
+        }
+        _ => empty_response(StatusCode::NOT_FOUND),
+    };
+    Ok(res)
 }
 
 /// The function which spawns the prometheus server
 ///
 /// F is generally a Notify awaiting a notification
-pub async fn prometheus_server<F>(port: u16, shutdown: F) -> hyper::Result<()>
+pub async fn prometheus_server<F>(port: u16, shutdown: F, ready: Readiness) -> hyper::Result<()>
 where
     F: Future<Output = ()>,
 {
     let addr = ([0, 0, 0, 0], port).into();
     info!("serving prometheus on http://{addr}");
 
-    let service = make_service_fn(move |_| async { Ok::<_, hyper::Error>(service_fn(metric_service)) });
+    let service = make_service_fn(move |_| {
+        let ready = ready.clone();
+        async move { Ok::<_, hyper::Error>(service_fn(move |req| route(req, ready.clone()))) }
+    });
     let err = Server::bind(&addr)
         .serve(service)
         .with_graceful_shutdown(shutdown)
@@ -74,7 +137,7 @@ async fn server_functions_and_shuts_down_gracefully() {
     let shutdown = Arc::new(Notify::new());
     let shutdown_clone = shutdown.clone();
     let server = tokio::spawn(async move {
-        prometheus_server(port, shutdown_clone.notified()).await.unwrap();
+        prometheus_server(port, shutdown_clone.notified(), Readiness::new()).await.unwrap();
     });
 
     let count = 7;
@@ -100,3 +163,60 @@ async fn server_functions_and_shuts_down_gracefully() {
     let ret = server.await;
     assert!(ret.is_ok())
 }
+
+#[tokio::test]
+async fn healthz_and_readyz_respond_before_and_after_ready() {
+    use hyper::Client;
+
+    let port = 1338;
+    let shutdown = Arc::new(tokio::sync::Notify::new());
+    let shutdown_clone = shutdown.clone();
+    let ready = Readiness::new();
+    let ready_clone = ready.clone();
+    let server = tokio::spawn(async move {
+        prometheus_server(port, shutdown_clone.notified(), ready_clone).await.unwrap();
+    });
+
+    let client = Client::new();
+
+    let res = client
+        .get(format!("http://localhost:{port}/healthz").parse().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client
+        .get(format!("http://localhost:{port}/readyz").parse().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    ready.set_ready();
+    let res = client
+        .get(format!("http://localhost:{port}/readyz").parse().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://localhost:{port}/metrics"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let res = client
+        .get(format!("http://localhost:{port}/nope").parse().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    shutdown.notify_one();
+    let ret = server.await;
+    assert!(ret.is_ok())
+}